@@ -0,0 +1,132 @@
+use crate::project::Project;
+use crate::utils::valid_tmux_identifier;
+
+use ignore::WalkBuilder;
+
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+// Config file names recognized as rmux project files during discovery
+const CONFIG_FILENAMES: &[&str] = &[".rmux.yml", ".rmux.yaml", "rmux.yml", "rmux.yaml"];
+
+// A project found while scanning a directory tree, either loaded from a
+// config file or synthesized for a directory that looks like a project but
+// has no config of its own.
+#[derive(Debug)]
+pub struct DiscoveredProject {
+    // The config file the project was loaded from, if any
+    pub config_path: Option<PathBuf>,
+    // The directory the project lives in
+    pub working_dir: PathBuf,
+    pub project: Project,
+}
+
+// A directory or config file that couldn't be read during a scan. These are
+// collected rather than aborting the whole traversal.
+#[derive(Debug)]
+pub struct DiscoveryError {
+    pub path: Option<PathBuf>,
+    pub error: Box<dyn Error>,
+}
+
+// The outcome of a discovery scan: the projects that could be loaded and the
+// per-entry errors that were skipped over.
+#[derive(Debug, Default)]
+pub struct Discovery {
+    pub projects: Vec<DiscoveredProject>,
+    pub errors: Vec<DiscoveryError>,
+}
+
+// Scans one or more root directories for project config files, respecting
+// `.gitignore`/`.ignore` rules. Dotted directories are skipped unless
+// `hidden` is set. Directories that look like projects (they contain a
+// `.git`) but have no config file synthesize a default Project.
+//
+// An unreadable directory or a malformed config file is recorded in `errors`
+// and skipped; it never aborts the traversal.
+pub fn discover(roots: &[PathBuf], hidden: bool) -> Discovery {
+    let mut discovery = Discovery::default();
+
+    let mut roots = roots.iter();
+    let first = match roots.next() {
+        Some(root) => root,
+        None => return discovery,
+    };
+
+    let mut builder = WalkBuilder::new(first);
+    for root in roots {
+        builder.add(root);
+    }
+    builder.hidden(!hidden).git_ignore(true).ignore(true);
+
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                discovery.errors.push(DiscoveryError {
+                    path: None,
+                    error: error.into(),
+                });
+                continue;
+            }
+        };
+        let path = entry.path();
+
+        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+
+        if let Some(config_path) = find_config(path) {
+            match load(&config_path) {
+                Ok(project) => discovery.projects.push(DiscoveredProject {
+                    config_path: Some(config_path),
+                    working_dir: path.to_path_buf(),
+                    project,
+                }),
+                Err(error) => discovery.errors.push(DiscoveryError {
+                    path: Some(config_path),
+                    error,
+                }),
+            }
+        } else if path.join(".git").exists() {
+            discovery.projects.push(DiscoveredProject {
+                config_path: None,
+                working_dir: path.to_path_buf(),
+                project: synthesize(path),
+            });
+        }
+    }
+
+    discovery
+}
+
+// Loads a single project config file through the Project deserializer
+fn load(config_path: &Path) -> Result<Project, Box<dyn Error>> {
+    let file = File::open(config_path)?;
+    Ok(serde_yaml::from_reader(file)?)
+}
+
+// Looks for a recognized config file directly inside the given directory
+fn find_config(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILENAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+// Builds a default Project for a config-less directory, deriving the session
+// name from the folder name (left unset when the folder name is not a valid
+// tmux identifier)
+fn synthesize(dir: &Path) -> Project {
+    let session_name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|name| valid_tmux_identifier(name).is_ok());
+
+    Project {
+        session_name,
+        working_dir: Some(dir.to_path_buf()),
+        ..Project::default()
+    }
+}
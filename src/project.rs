@@ -1,5 +1,6 @@
 use crate::command::de_command_list;
 use crate::config::Config;
+use crate::pane::Pane;
 use crate::project_template::ProjectTemplate;
 use crate::startup_window::StartupWindow;
 use crate::utils::{parse_command, valid_tmux_identifier};
@@ -9,9 +10,11 @@ use crate::working_dir::de_working_dir;
 use serde::{de, Deserialize, Serialize};
 use shell_words::{quote, split};
 
+use std::env::current_dir;
 use std::error::Error;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct Project {
@@ -35,14 +38,32 @@ pub struct Project {
     pub post_pane_create: Vec<String>,
     pub pane_commands: Vec<String>,
     pub attach: bool,
+    pub attach_read_only: bool,
+    pub detach_other: bool,
+    pub force_new_session: bool,
+    pub git_repo_session_name: bool,
     pub template: ProjectTemplate,
     pub windows: Vec<Window>,
 }
 
 impl Project {
-    pub fn prepare(self, config: &Config, project_name: &str, force_attach: Option<bool>) -> Self {
+    pub fn prepare(
+        self,
+        config: &Config,
+        project_name: &str,
+        force_attach: Option<bool>,
+        force_new_session: Option<bool>,
+    ) -> Self {
+        let session_name = self.session_name.clone().or_else(|| {
+            if self.git_repo_session_name {
+                git_repo_session_name(self.working_dir.as_deref())
+            } else {
+                None
+            }
+        });
+
         let mut project = Self {
-            session_name: self.session_name.or(Some(project_name.into())),
+            session_name: session_name.or(Some(project_name.into())),
             ..self
         };
 
@@ -50,21 +71,72 @@ impl Project {
             project.attach = attach;
         }
 
+        if let Some(force_new_session) = force_new_session {
+            project.force_new_session = force_new_session;
+        }
+
         if let Some(tmux_command) = &config.tmux_command {
             project.tmux_command = Some(tmux_command.to_string_lossy().into());
         } else if project.tmux_command.is_none() {
             project.tmux_command = Some("tmux".into());
         }
 
+        // In "always new session" mode, make sure we don't collide with an
+        // already-running session of the same name by appending a numeric
+        // suffix until we find a free name
+        if project.force_new_session {
+            if let Some(base) = project.session_name.clone() {
+                if project.session_exists(&base) {
+                    let mut suffix = 1;
+                    let name = loop {
+                        let candidate = format!("{}-{}", base, suffix);
+                        if !project.session_exists(&candidate) {
+                            break candidate;
+                        }
+                        suffix += 1;
+                    };
+                    project.session_name = Some(name);
+                }
+            }
+        }
+
         project
     }
 
+    // Returns whether a tmux session with the given name already exists
+    fn session_exists(&self, name: &str) -> bool {
+        let args = vec![
+            OsString::from("has-session"),
+            OsString::from("-t"),
+            OsString::from(format!("={}", name)),
+        ];
+
+        match self.get_tmux_command(args) {
+            Ok((command, args)) => Command::new(command)
+                .args(args)
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
     pub fn check(&self) -> Result<(), Box<dyn Error>> {
         // Make sure session name is valid
         if let Some(session_name) = &self.session_name {
             valid_tmux_identifier(session_name)?;
         }
 
+        // Attach modifiers only make sense when the session is attached to
+        if !self.attach {
+            if self.attach_read_only {
+                Err("attach_read_only is set but attach is false")?;
+            }
+            if self.detach_other {
+                Err("detach_other is set but attach is false")?;
+            }
+        }
+
         // Make sure start up window exists
         match &self.startup_window {
             StartupWindow::Index(index) => {
@@ -146,6 +218,27 @@ impl Project {
         parse_command(&command, &full_args)
     }
 
+    // Builds the attach-session argument vector, honoring the read-only (-r)
+    // and detach-other (-d) attach modifiers
+    pub fn get_attach_command(&self) -> Result<(OsString, Vec<OsString>), Box<dyn Error>> {
+        let mut args = vec![OsString::from("attach-session")];
+
+        if self.attach_read_only {
+            args.push(OsString::from("-r"));
+        }
+
+        if self.detach_other {
+            args.push(OsString::from("-d"));
+        }
+
+        if let Some(session_name) = &self.session_name {
+            args.push(OsString::from("-t"));
+            args.push(OsString::from(session_name));
+        }
+
+        self.get_tmux_command(args)
+    }
+
     // Sanitizes tmux_command for use in the template file
     pub fn get_tmux_command_for_template(&self) -> Result<String, Box<dyn Error>> {
         let (command, args) = self.get_tmux_command(vec![])?;
@@ -160,6 +253,158 @@ impl Project {
             .join(" "))
     }
 
+    // Captures a running tmux session back into a Project
+    //
+    // Queries tmux for the session's windows and panes and reconstructs a
+    // Project that, once serialized, round-trips back through
+    // Project::deserialize. This lets users snapshot an interactively-built
+    // session and commit it as a template.
+    pub fn freeze(config: &Config, session_name: &str) -> Result<Self, Box<dyn Error>> {
+        // Borrow the command resolution logic from a prepared project
+        let project = Self::default().prepare(config, session_name, Some(false), Some(false));
+
+        // Read the session's base indices from the tmux options. These are not
+        // available as format variables, so they have to be queried with
+        // show-options (base-index is a session option, pane-base-index a
+        // window option)
+        // Fall back to tmux's real default of 0 (not rmux's config default of
+        // 1) when the option isn't reported, so the base stays consistent with
+        // windows/panes that start at 0
+        let window_base_index = project
+            .tmux_show_option(session_name, &["-gv", "base-index"])?
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(0);
+        let pane_base_index = project
+            .tmux_show_option(session_name, &["-gwv", "pane-base-index"])?
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(0);
+
+        // Reconstruct each window together with its panes. A tab is used as
+        // the field delimiter since it cannot occur in a window name, layout
+        // or path, unlike ':'
+        let windows_output = project.tmux_capture(vec![
+            OsString::from("list-windows"),
+            OsString::from("-t"),
+            OsString::from(session_name),
+            OsString::from("-F"),
+            OsString::from("#{window_index}\t#{window_name}\t#{window_layout}\t#{window_active}"),
+        ])?;
+
+        let mut windows = vec![];
+        let mut active_window_position = None;
+        for line in windows_output.lines().filter(|l| !l.is_empty()) {
+            let mut fields = line.splitn(4, '\t');
+            let index = fields.next().unwrap_or("");
+            let name = fields.next().unwrap_or("");
+            let layout = fields.next().unwrap_or("");
+            let active = fields.next().unwrap_or("");
+
+            // Record the ordinal position rather than the raw tmux index:
+            // rmux recreates windows contiguously from window_base_index, so a
+            // raw index from a gapped session would fail check()
+            if active == "1" {
+                active_window_position = Some(windows.len());
+            }
+
+            let panes_output = project.tmux_capture(vec![
+                OsString::from("list-panes"),
+                OsString::from("-t"),
+                OsString::from(format!("{}:{}", session_name, index)),
+                OsString::from("-F"),
+                OsString::from("#{pane_index}\t#{pane_current_path}"),
+            ])?;
+
+            let panes = panes_output
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(|line| {
+                    let mut fields = line.splitn(2, '\t');
+                    let _index = fields.next();
+                    let working_dir = fields.next().filter(|s| !s.is_empty()).map(PathBuf::from);
+                    Pane {
+                        working_dir,
+                        ..Pane::default()
+                    }
+                })
+                .collect();
+
+            windows.push(Window {
+                name: (!name.is_empty()).then(|| name.to_string()),
+                layout: (!layout.is_empty()).then(|| layout.to_string()),
+                panes,
+                ..Window::default()
+            });
+        }
+
+        Ok(Self::freeze_from_parts(
+            session_name,
+            window_base_index,
+            pane_base_index,
+            active_window_position,
+            windows,
+        ))
+    }
+
+    // Assembles a frozen Project from the values captured off a running tmux
+    // session. Kept separate from the tmux queries so it can be exercised
+    // without a live server.
+    //
+    // active_window_position is the ordinal position of the active window in
+    // the captured windows vec, which maps to a contiguous recreated index of
+    // window_base_index + position.
+    fn freeze_from_parts(
+        session_name: &str,
+        window_base_index: usize,
+        pane_base_index: usize,
+        active_window_position: Option<usize>,
+        windows: Vec<Window>,
+    ) -> Self {
+        Self {
+            session_name: Some(session_name.into()),
+            window_base_index,
+            pane_base_index,
+            startup_window: match active_window_position {
+                Some(position) => StartupWindow::Index(window_base_index + position),
+                None => StartupWindow::default(),
+            },
+            windows,
+            ..Self::default()
+        }
+    }
+
+    // Reads a single tmux option value via show-options, returning None when
+    // the option is unset
+    fn tmux_show_option(
+        &self,
+        session_name: &str,
+        option_args: &[&str],
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let mut args = vec![OsString::from("show-options")];
+        args.extend(option_args.iter().map(OsString::from));
+        args.push(OsString::from("-t"));
+        args.push(OsString::from(session_name));
+
+        let output = self.tmux_capture(args)?;
+        let value = output.trim();
+        Ok((!value.is_empty()).then(|| value.to_string()))
+    }
+
+    // Runs a tmux subcommand and returns its standard output as a string
+    fn tmux_capture(&self, args: Vec<OsString>) -> Result<String, Box<dyn Error>> {
+        let (command, args) = self.get_tmux_command(args)?;
+
+        let output = Command::new(command).args(args).output()?;
+        if !output.status.success() {
+            Err(format!(
+                "tmux exited with status {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))?;
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
     fn default_window_base_index() -> usize {
         1
     }
@@ -176,6 +421,10 @@ impl Project {
         true
     }
 
+    fn default_git_repo_session_name() -> bool {
+        true
+    }
+
     fn de_window_base_index<'de, D>(deserializer: D) -> Result<usize, D::Error>
     where
         D: de::Deserializer<'de>,
@@ -237,12 +486,34 @@ impl Default for Project {
             post_pane_create: vec![],
             pane_commands: vec![],
             attach: true,
+            attach_read_only: false,
+            detach_other: false,
+            force_new_session: false,
+            git_repo_session_name: Self::default_git_repo_session_name(),
             template: ProjectTemplate::default(),
             windows: Self::default_windows(),
         }
     }
 }
 
+// Walks up from the given directory (or the current directory) looking for a
+// Git repository root, returning its directory name unchanged, or None when
+// that name is not a valid tmux session name.
+fn git_repo_session_name(working_dir: Option<&Path>) -> Option<String> {
+    let start = match working_dir {
+        Some(path) => path.to_path_buf(),
+        None => current_dir().ok()?,
+    };
+
+    let repo_root = start
+        .ancestors()
+        .find(|path| path.join(".git").exists())?;
+
+    let name = repo_root.file_name()?.to_string_lossy().into_owned();
+
+    valid_tmux_identifier(&name).ok().map(|_| name)
+}
+
 impl From<Option<Project>> for Project {
     fn from(project: Option<Project>) -> Self {
         project.unwrap_or_default()
@@ -330,6 +601,14 @@ impl<'de> Deserialize<'de> for Project {
             attach: Option<bool>,
             #[serde(default, alias = "tmux_detached")]
             detached: Option<bool>,
+            #[serde(default, alias = "read_only")]
+            attach_read_only: bool,
+            #[serde(default)]
+            detach_other: bool,
+            #[serde(default, alias = "always_new_session")]
+            force_new_session: bool,
+            #[serde(default = "Project::default_git_repo_session_name")]
+            git_repo_session_name: bool,
             #[serde(default)]
             template: ProjectTemplate,
             #[serde(
@@ -379,6 +658,10 @@ impl<'de> Deserialize<'de> for Project {
                     post_pane_create: project.post_pane_create,
                     pane_commands: project.pane_commands,
                     attach,
+                    attach_read_only: project.attach_read_only,
+                    detach_other: project.detach_other,
+                    force_new_session: project.force_new_session,
+                    git_repo_session_name: project.git_repo_session_name,
                     template: project.template,
                     windows: project.windows,
                 }
@@ -0,0 +1,64 @@
+use super::*;
+
+#[test]
+fn freeze_base_index_0_passes_check() {
+    // A session whose base-index is 0 has its active window at index 0 too.
+    // The assembled project must stay internally consistent so the template
+    // it serializes to passes check() when reloaded.
+    let project = Project::freeze_from_parts("session", 0, 0, Some(0), vec![Window::default()]);
+
+    assert_eq!(project.window_base_index, 0);
+    assert_eq!(project.startup_window, StartupWindow::Index(0));
+    assert!(project.check().is_ok());
+}
+
+#[test]
+fn freeze_active_window_uses_ordinal_position() {
+    // Real sessions have gaps (e.g. raw indices 0, 2 after closing window 1).
+    // The active window must be recorded by its position in the captured vec,
+    // not its raw tmux index, so the contiguously-recreated project still
+    // passes check().
+    let project = Project::freeze_from_parts(
+        "session",
+        0,
+        0,
+        Some(1),
+        vec![Window::default(), Window::default()],
+    );
+
+    assert_eq!(project.startup_window, StartupWindow::Index(1));
+    assert!(project.check().is_ok());
+}
+
+#[test]
+fn attach_command_threads_read_only_and_detach_flags() {
+    let project = Project {
+        session_name: Some("session".into()),
+        tmux_command: Some("tmux".into()),
+        attach_read_only: true,
+        detach_other: true,
+        ..Project::default()
+    };
+
+    let (_command, args) = project.get_attach_command().unwrap();
+    let args: Vec<_> = args.iter().filter_map(|a| a.to_str()).collect();
+
+    assert!(args.contains(&"attach-session"));
+    assert!(args.contains(&"-r"));
+    assert!(args.contains(&"-d"));
+}
+
+#[test]
+fn prepare_accepts_force_new_session_argument() {
+    // Guards the prepare() signature: callers pass force_new_session
+    // alongside force_attach.
+    let config = Config::default();
+    let project = Project {
+        git_repo_session_name: false,
+        ..Project::default()
+    }
+    .prepare(&config, "proj", None, Some(false));
+
+    assert_eq!(project.session_name, Some("proj".into()));
+    assert!(!project.force_new_session);
+}